@@ -0,0 +1,85 @@
+use crate::{decode_u32, DecodeError};
+
+/// An iterator that decodes successive extended UTF-8 sequences out of a byte slice.
+///
+/// Constructed via [`decode_iter`](crate::decode_iter). Each call to
+/// [`next`](Iterator::next) decodes one sequence starting at the current position
+/// and advances the cursor past it. Once a sequence fails to decode, the iterator
+/// yields that one [`DecodeError`] and then stops, just as it does at the end of
+/// the slice.
+#[derive(Clone, Debug)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Decoder { bytes, pos: 0 }
+    }
+
+    /// Returns the current byte offset into the original slice.
+    pub fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> Iterator for Decoder<'a> {
+    type Item = Result<u32, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos >= self.bytes.len() {
+            return None;
+        }
+
+        match decode_u32(&self.bytes[self.pos..]) {
+            Ok((codepoint, len)) => {
+                self.pos += len;
+                Some(Ok(codepoint))
+            }
+            Err(err) => {
+                // don't loop forever on the same malformed byte
+                self.pos = self.bytes.len();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::decode_iter;
+
+    #[test]
+    fn iterates_over_a_document() {
+        let bytes = [b'a', 0xc2, 0xa9, 0xe2, 0x89, 0xa0];
+        let mut iter = decode_iter(&bytes);
+
+        assert_eq!(iter.next(), Some(Ok(b'a' as u32)));
+        assert_eq!(iter.next(), Some(Ok(0xa9)));
+        assert_eq!(iter.next(), Some(Ok(0x2260)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn stops_after_an_error() {
+        let bytes = [b'a', 0x80, b'b'];
+        let mut iter = decode_iter(&bytes);
+
+        assert_eq!(iter.next(), Some(Ok(b'a' as u32)));
+        assert!(iter.next().unwrap().is_err());
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn tracks_position() {
+        let bytes = [b'a', 0xc2, 0xa9];
+        let mut iter = decode_iter(&bytes);
+
+        assert_eq!(iter.pos(), 0);
+        iter.next();
+        assert_eq!(iter.pos(), 1);
+        iter.next();
+        assert_eq!(iter.pos(), 3);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{decode_length, encode_u32, get_header, Codepoint, DecodeError, OverflowError};
+
+/// Extension methods on `u8` for inspecting extended UTF-8 lead bytes, without
+/// needing to decode a whole sequence first.
+pub trait U8UtfExt {
+    /// Returns the number of continuation bytes that follow this lead byte: 0 for
+    /// ASCII, up to 5 for a 6-byte `0b1111110x` lead.
+    ///
+    /// ## Errors
+    /// Returns a [`DecodeError`] if `self` cannot start a sequence, i.e. it's itself
+    /// a continuation byte (`0b10xxxxxx`) or one of the two bytes RFC 2279 never
+    /// assigns a meaning to (`0b11111110`/`0b11111111`).
+    fn extra_bytes(self) -> Result<usize, DecodeError>;
+
+    /// Returns `true` if this byte is a continuation byte (`0b10xxxxxx`).
+    fn is_continuation(&self) -> bool;
+}
+
+impl U8UtfExt for u8 {
+    fn extra_bytes(self) -> Result<usize, DecodeError> {
+        decode_length(self).map(|length| length - 1)
+    }
+
+    fn is_continuation(&self) -> bool {
+        self & 0b1100_0000 == 0b1000_0000
+    }
+}
+
+/// Extension methods for treating a value as an extended UTF-8 codepoint, implemented
+/// for both `u32` (the crate's native representation) and `char`.
+pub trait CharExt {
+    /// Returns the length (in bytes) of this codepoint's extended UTF-8 encoding.
+    ///
+    /// This is the length half of [`get_header`].
+    ///
+    /// ## Errors
+    /// Returns an [`OverflowError`] under the same conditions as [`get_header`].
+    fn len_utf8_rfc2279(&self) -> Result<usize, OverflowError>;
+
+    /// Encodes this codepoint as extended UTF-8.
+    ///
+    /// ## Errors
+    /// Returns an [`OverflowError`] under the same conditions as [`encode_u32`].
+    fn encode_rfc2279(&self) -> Result<Codepoint, OverflowError>;
+}
+
+impl CharExt for u32 {
+    fn len_utf8_rfc2279(&self) -> Result<usize, OverflowError> {
+        get_header(*self).map(|(length, _)| length)
+    }
+
+    fn encode_rfc2279(&self) -> Result<Codepoint, OverflowError> {
+        encode_u32(*self)
+    }
+}
+
+impl CharExt for char {
+    fn len_utf8_rfc2279(&self) -> Result<usize, OverflowError> {
+        (*self as u32).len_utf8_rfc2279()
+    }
+
+    fn encode_rfc2279(&self) -> Result<Codepoint, OverflowError> {
+        (*self as u32).encode_rfc2279()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CharExt, U8UtfExt};
+    use crate::DecodeError;
+
+    #[test]
+    fn extra_bytes_matches_sequence_length() {
+        assert_eq!(b'a'.extra_bytes(), Ok(0));
+        assert_eq!(0xc2u8.extra_bytes(), Ok(1));
+        assert_eq!(0xe2u8.extra_bytes(), Ok(2));
+        assert_eq!(0xfdu8.extra_bytes(), Ok(5));
+        assert_eq!(0x80u8.extra_bytes(), Err(DecodeError::InvalidLeadByte));
+        assert_eq!(0xffu8.extra_bytes(), Err(DecodeError::InvalidLeadByte));
+    }
+
+    #[test]
+    fn is_continuation_works() {
+        assert!(0x80u8.is_continuation());
+        assert!(0xbfu8.is_continuation());
+        assert!(!b'a'.is_continuation());
+        assert!(!0xc2u8.is_continuation());
+    }
+
+    #[test]
+    fn len_and_encode_agree_for_u32_and_char() {
+        assert_eq!(0x2260u32.len_utf8_rfc2279(), Ok(3));
+        assert_eq!('≠'.len_utf8_rfc2279(), Ok(3));
+        assert_eq!(
+            0x2260u32.encode_rfc2279().unwrap(),
+            '≠'.encode_rfc2279().unwrap()
+        );
+    }
+}
@@ -0,0 +1,157 @@
+use core::convert::TryFrom;
+use core::ops::Deref;
+
+use crate::{decode_u32, encode_u32_slice, DecodeError, EncodeError, OverflowError};
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct InvalidCharError;
+
+/// A validated, stack-allocated extended UTF-8 character.
+///
+/// Unlike the free [`encode_u32`](crate::encode_u32)/[`decode_u32`](crate::decode_u32)
+/// functions, a `FullUtf8Char` is guaranteed by construction to hold exactly one
+/// well-formed, non-overlong sequence, analogous to `encode_unicode`'s `Utf8Char`.
+///
+/// Note that because longer sequences always start with more leading one bits, the
+/// derived [`Ord`] compares `FullUtf8Char`s in the same order as their codepoints,
+/// without needing to decode them first.
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FullUtf8Char {
+    bytes: [u8; 6],
+    len: u8,
+}
+
+impl FullUtf8Char {
+    /// Encodes `codepoint` into a `FullUtf8Char`.
+    ///
+    /// ## Errors
+    /// Returns an [`OverflowError`] under the same conditions as
+    /// [`encode_u32`](crate::encode_u32).
+    pub fn from_u32(codepoint: u32) -> Result<Self, OverflowError> {
+        let mut bytes = [0u8; 6];
+        let len = match encode_u32_slice(codepoint, &mut bytes) {
+            Ok(len) => len,
+            Err(EncodeError::Overflow(err)) => return Err(err),
+            Err(EncodeError::BufferTooShort) => unreachable!("a 6-byte buffer always fits"),
+        };
+
+        Ok(FullUtf8Char {
+            bytes,
+            len: len as u8,
+        })
+    }
+
+    /// Decodes a single `FullUtf8Char` from the start of `bytes`, returning it
+    /// along with the number of bytes consumed.
+    ///
+    /// ## Errors
+    /// Returns a [`DecodeError`] under the same conditions as
+    /// [`decode_u32`](crate::decode_u32).
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let (_, consumed) = decode_u32(bytes)?;
+
+        let mut out = [0u8; 6];
+        out[..consumed].copy_from_slice(&bytes[..consumed]);
+
+        Ok((
+            FullUtf8Char {
+                bytes: out,
+                len: consumed as u8,
+            },
+            consumed,
+        ))
+    }
+
+    /// Returns the decoded codepoint.
+    pub fn to_u32(self) -> u32 {
+        // infallible: `self` only ever holds either the output of `encode_u32_slice`
+        // or a sequence that already passed `decode_u32`'s validation
+        decode_u32(self.as_bytes()).unwrap().0
+    }
+
+    /// Returns the number of bytes in the sequence (from 1 to 6).
+    pub fn len(self) -> usize {
+        self.len as usize
+    }
+
+    /// Always `false`: a `FullUtf8Char` holds exactly one sequence, which is never empty.
+    pub fn is_empty(self) -> bool {
+        false
+    }
+
+    /// Returns the encoded byte sequence.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len as usize]
+    }
+}
+
+impl Deref for FullUtf8Char {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl TryFrom<char> for FullUtf8Char {
+    type Error = OverflowError;
+
+    fn try_from(c: char) -> Result<Self, OverflowError> {
+        FullUtf8Char::from_u32(c as u32)
+    }
+}
+
+impl TryFrom<FullUtf8Char> for char {
+    type Error = InvalidCharError;
+
+    /// Fails for codepoints above `U+10FFFF` or in the surrogate range
+    /// `U+D800..=U+DFFF`, neither of which are valid `char`s.
+    fn try_from(c: FullUtf8Char) -> Result<char, InvalidCharError> {
+        char::from_u32(c.to_u32()).ok_or(InvalidCharError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use super::FullUtf8Char;
+
+    #[test]
+    fn roundtrips_through_u32() {
+        for codepoint in [0, b'a' as u32, 0xa9, 0x2260, 0x7DEADA55] {
+            let c = FullUtf8Char::from_u32(codepoint).unwrap();
+            assert_eq!(c.to_u32(), codepoint);
+        }
+    }
+
+    #[test]
+    fn roundtrips_through_bytes() {
+        let bytes = [0xe2, 0x89, 0xa0, 0xff];
+        let (c, len) = FullUtf8Char::from_bytes(&bytes).unwrap();
+
+        assert_eq!(len, 3);
+        assert_eq!(&*c, &bytes[..3]);
+        assert_eq!(c.to_u32(), 0x2260);
+    }
+
+    #[test]
+    fn converts_to_and_from_char() {
+        let c = FullUtf8Char::try_from('≠').unwrap();
+        assert_eq!(char::try_from(c).unwrap(), '≠');
+
+        let surrogate = FullUtf8Char::from_u32(0xD800).unwrap();
+        assert!(char::try_from(surrogate).is_err());
+
+        let too_large = FullUtf8Char::from_u32(0x7DEADA55).unwrap();
+        assert!(char::try_from(too_large).is_err());
+    }
+
+    #[test]
+    fn orders_like_codepoints() {
+        let small = FullUtf8Char::from_u32(0xa9).unwrap();
+        let big = FullUtf8Char::from_u32(0x2260).unwrap();
+
+        assert!(small < big);
+    }
+}
@@ -0,0 +1,292 @@
+//! Adapters bridging this crate's extended (RFC 2279) 6-byte scheme and modern
+//! RFC 3629 `str`, in the spirit of `ripgrep`'s move to `encoding_rs_io`'s
+//! `Read`-implementing transcoder.
+
+use std::io::{self, Read, Write};
+
+use crate::{decode_length, decode_u32, DecodeError, EncodeError};
+
+/// Reads a stream of extended UTF-8 bytes and exposes it as a reader of standard
+/// UTF-8 bytes, decoding and re-encoding one codepoint at a time.
+///
+/// A sequence that straddles two `read` calls on the underlying reader is buffered
+/// internally, so the transcoded stream never loses or duplicates a partial codepoint.
+///
+/// ## Errors
+/// Reads fail with [`io::ErrorKind::InvalidData`] both for malformed extended UTF-8
+/// (see [`DecodeError`]) and for codepoints above `U+10FFFF` or in the surrogate
+/// range `U+D800..=U+DFFF`, which standard UTF-8 cannot represent. They fail with
+/// [`io::ErrorKind::UnexpectedEof`] when the underlying reader ends partway through
+/// a sequence.
+pub struct ReadTranscoder<R> {
+    inner: R,
+    raw: [u8; 6],
+    raw_len: usize,
+    out: [u8; 4],
+    out_pos: usize,
+    out_len: usize,
+}
+
+impl<R: Read> ReadTranscoder<R> {
+    /// Wraps `inner`, interpreting its bytes as an extended UTF-8 stream.
+    pub fn new(inner: R) -> Self {
+        ReadTranscoder {
+            inner,
+            raw: [0; 6],
+            raw_len: 0,
+            out: [0; 4],
+            out_pos: 0,
+            out_len: 0,
+        }
+    }
+
+    /// Unwraps this transcoder, returning the underlying reader.
+    ///
+    /// Any partially-read sequence buffered internally is discarded.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Decodes the next codepoint from `inner`, returning `Ok(None)` at a clean end of stream.
+    fn next_char(&mut self) -> io::Result<Option<char>> {
+        loop {
+            match decode_u32(&self.raw[..self.raw_len]) {
+                Ok((codepoint, consumed)) => {
+                    self.raw.copy_within(consumed..self.raw_len, 0);
+                    self.raw_len -= consumed;
+
+                    return char::from_u32(codepoint).map(Some).ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            format!(
+                                "codepoint {:#x} has no standard UTF-8 representation",
+                                codepoint
+                            ),
+                        )
+                    });
+                }
+                Err(DecodeError::UnexpectedEnd) if self.raw_len == 0 => {
+                    // we don't yet know the sequence length, so read just the lead byte
+                    if self.inner.read(&mut self.raw[..1])? == 0 {
+                        return Ok(None);
+                    }
+                    self.raw_len = 1;
+                }
+                Err(DecodeError::UnexpectedEnd) => {
+                    // the lead byte already tells us exactly how many bytes to expect,
+                    // so the rest of the sequence can be read in a single syscall
+                    // instead of one byte at a time
+                    let needed = decode_length(self.raw[0]).expect("lead byte already validated");
+                    while self.raw_len < needed {
+                        let n = self.inner.read(&mut self.raw[self.raw_len..needed])?;
+                        if n == 0 {
+                            return Err(io::Error::new(
+                                io::ErrorKind::UnexpectedEof,
+                                "truncated extended UTF-8 sequence",
+                            ));
+                        }
+                        self.raw_len += n;
+                    }
+                }
+                Err(err) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("{:?}", err),
+                    ))
+                }
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for ReadTranscoder<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.out_pos == self.out_len {
+            match self.next_char()? {
+                Some(c) => {
+                    self.out_len = c.encode_utf8(&mut self.out).len();
+                    self.out_pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+
+        let n = (self.out_len - self.out_pos).min(buf.len());
+        buf[..n].copy_from_slice(&self.out[self.out_pos..self.out_pos + n]);
+        self.out_pos += n;
+        Ok(n)
+    }
+}
+
+/// Accepts standard UTF-8 bytes and writes their extended UTF-8 re-encoding to the
+/// wrapped writer.
+///
+/// A `str` boundary that straddles two `write` calls is buffered internally, so
+/// callers can write in arbitrarily small chunks without losing or duplicating a
+/// partial codepoint.
+pub struct WriteTranscoder<W> {
+    inner: W,
+    buf: [u8; 4],
+    buf_len: usize,
+}
+
+impl<W: Write> WriteTranscoder<W> {
+    /// Wraps `inner`, re-encoding everything written through this adapter as
+    /// extended UTF-8 before forwarding it.
+    pub fn new(inner: W) -> Self {
+        WriteTranscoder {
+            inner,
+            buf: [0; 4],
+            buf_len: 0,
+        }
+    }
+
+    /// Unwraps this transcoder, returning the underlying writer.
+    ///
+    /// Any partially-buffered `str` bytes are discarded.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    fn emit(&mut self, c: char) -> io::Result<()> {
+        let mut out = [0u8; 6];
+        let len = match crate::encode_u32_slice(c as u32, &mut out) {
+            Ok(len) => len,
+            Err(EncodeError::Overflow(_)) => {
+                unreachable!("a char's codepoint always fits in 31 bits")
+            }
+            Err(EncodeError::BufferTooShort) => unreachable!("a 6-byte buffer always fits"),
+        };
+        self.inner.write_all(&out[..len])
+    }
+}
+
+impl<W: Write> Write for WriteTranscoder<W> {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        let mut input = data;
+
+        if self.buf_len > 0 {
+            let want = std_utf8_len(self.buf[0]) - self.buf_len;
+            let take = want.min(input.len());
+            self.buf[self.buf_len..self.buf_len + take].copy_from_slice(&input[..take]);
+            self.buf_len += take;
+            input = &input[take..];
+
+            if self.buf_len == std_utf8_len(self.buf[0]) {
+                let c = core::str::from_utf8(&self.buf[..self.buf_len])
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?
+                    .chars()
+                    .next()
+                    .unwrap();
+                self.emit(c)?;
+                self.buf_len = 0;
+            }
+        }
+
+        match core::str::from_utf8(input) {
+            Ok(s) => {
+                for c in s.chars() {
+                    self.emit(c)?;
+                }
+            }
+            Err(err) => {
+                let valid_up_to = err.valid_up_to();
+                for c in core::str::from_utf8(&input[..valid_up_to]).unwrap().chars() {
+                    self.emit(c)?;
+                }
+
+                if err.error_len().is_some() {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+                }
+
+                // an incomplete sequence trails `input`; buffer it for the next call
+                let rest = &input[valid_up_to..];
+                self.buf[..rest.len()].copy_from_slice(rest);
+                self.buf_len = rest.len();
+            }
+        }
+
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Returns the length (in bytes) of the standard UTF-8 sequence starting with `lead`.
+fn std_utf8_len(lead: u8) -> usize {
+    match lead {
+        0b0000_0000..=0b0111_1111 => 1,
+        0b1100_0000..=0b1101_1111 => 2,
+        0b1110_0000..=0b1110_1111 => 3,
+        _ => 4,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read, Write};
+
+    use super::{ReadTranscoder, WriteTranscoder};
+
+    #[test]
+    fn read_transcoder_decodes_extended_utf8() {
+        let rfc2279 = [b'a', 0xc2, 0xa9, 0xe2, 0x89, 0xa0];
+        let mut transcoder = ReadTranscoder::new(Cursor::new(rfc2279));
+
+        let mut out = String::new();
+        transcoder.read_to_string(&mut out).unwrap();
+
+        assert_eq!(out, "a\u{a9}\u{2260}");
+    }
+
+    #[test]
+    fn read_transcoder_handles_byte_at_a_time_reads() {
+        struct OneByteAtATime<'a>(&'a [u8]);
+
+        impl Read for OneByteAtATime<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() || buf.is_empty() {
+                    return Ok(0);
+                }
+                buf[0] = self.0[0];
+                self.0 = &self.0[1..];
+                Ok(1)
+            }
+        }
+
+        let rfc2279 = [0xe2, 0x89, 0xa0];
+        let mut transcoder = ReadTranscoder::new(OneByteAtATime(&rfc2279));
+
+        let mut out = String::new();
+        transcoder.read_to_string(&mut out).unwrap();
+
+        assert_eq!(out, "\u{2260}");
+    }
+
+    #[test]
+    fn write_transcoder_encodes_to_extended_utf8() {
+        let mut buf = Vec::new();
+        {
+            let mut transcoder = WriteTranscoder::new(&mut buf);
+            transcoder.write_all("a\u{a9}\u{2260}".as_bytes()).unwrap();
+        }
+
+        assert_eq!(buf, [b'a', 0xc2, 0xa9, 0xe2, 0x89, 0xa0]);
+    }
+
+    #[test]
+    fn write_transcoder_handles_split_writes() {
+        let input = "\u{2260}".as_bytes();
+        let mut buf = Vec::new();
+        {
+            let mut transcoder = WriteTranscoder::new(&mut buf);
+            for chunk in input {
+                transcoder.write_all(&[*chunk]).unwrap();
+            }
+        }
+
+        assert_eq!(buf, [0xe2, 0x89, 0xa0]);
+    }
+}
@@ -1,4 +1,16 @@
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+mod decoder;
+mod ext;
+mod full_utf8_char;
+#[cfg(feature = "std")]
+mod io;
+
+pub use decoder::Decoder;
+pub use ext::{CharExt, U8UtfExt};
+pub use full_utf8_char::{FullUtf8Char, InvalidCharError};
+#[cfg(feature = "std")]
+pub use crate::io::{ReadTranscoder, WriteTranscoder};
 
 #[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct OverflowError;
@@ -44,6 +56,173 @@ pub fn encode_u32(mut input: u32) -> Result<Codepoint, OverflowError> {
     Ok(output)
 }
 
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum EncodeError {
+    /// The input has its 32nd (as in, most significant) bit set.
+    Overflow(OverflowError),
+    /// The output buffer is shorter than the number of bytes the input encodes to.
+    BufferTooShort,
+}
+
+impl From<OverflowError> for EncodeError {
+    fn from(err: OverflowError) -> Self {
+        EncodeError::Overflow(err)
+    }
+}
+
+/// Encodes the input as a UTF-8 byte sequence, writing it directly into `out`
+/// starting at index 0, and returns the number of bytes written (from 1 to 6).
+///
+/// Unlike [`encode_u32`], this writes into a caller-provided buffer instead of
+/// returning a [`Codepoint`], which suits encoding many codepoints back-to-back
+/// into one preallocated buffer.
+///
+/// ## Errors
+/// Returns [`EncodeError::Overflow`] under the same conditions as [`encode_u32`],
+/// and [`EncodeError::BufferTooShort`] when `out` is too small to hold the encoded
+/// sequence.
+///
+/// ## Example
+/// ```rust
+/// # fn main() {
+/// let not_equal = 0x2260; // U+2260 corresponds to `≠`
+/// let mut buf = [0u8; 6];
+/// let len = utf8_rfc2279::encode_u32_slice(not_equal, &mut buf).unwrap();
+///
+/// assert_eq!(&buf[..len], &[0xE2, 0x89, 0xA0]);
+/// # }
+/// ```
+pub fn encode_u32_slice(mut input: u32, out: &mut [u8]) -> Result<usize, EncodeError> {
+    let (length, header) = get_header(input)?;
+
+    if out.len() < length {
+        return Err(EncodeError::BufferTooShort);
+    }
+
+    for i in (1..length).rev() {
+        out[i] = 0b1000_0000 | (input & 0b0011_1111) as u8;
+        input >>= 6;
+    }
+    out[0] = header | input as u8; // by now, input fits in the remaining space
+
+    Ok(length)
+}
+
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum DecodeError {
+    /// The slice ended before all of the bytes indicated by the lead byte were available.
+    UnexpectedEnd,
+    /// A byte in a continuation position did not match the `0b10xxxxxx` pattern.
+    InvalidContinuationByte,
+    /// The lead byte cannot start a sequence: either it's itself a continuation byte
+    /// (`0b10xxxxxx`), or it's one of the two bytes RFC 2279 never assigns a meaning to
+    /// (`0b11111110`/`0b11111111`).
+    InvalidLeadByte,
+    /// The sequence decoded to a codepoint smaller than the shortest encoding of that
+    /// length allows.
+    Overlong,
+}
+
+/// Decodes a single extended UTF-8 byte sequence from the start of `bytes`.
+///
+/// On success, returns the decoded codepoint along with the number of bytes
+/// consumed from `bytes` (i.e. the length of the sequence, from 1 to 6).
+///
+/// ## Errors
+/// Returns a [`DecodeError`] when `bytes` is empty or truncated, when a continuation
+/// byte is malformed, when the lead byte cannot start a sequence, or when the
+/// sequence is an overlong encoding of a codepoint that a shorter sequence could
+/// have represented.
+///
+/// ## Example
+/// ```rust
+/// # fn main() {
+/// let bytes = [0xE2, 0x89, 0xA0]; // U+2260 corresponds to `≠`
+/// let (codepoint, length) = utf8_rfc2279::decode_u32(&bytes).unwrap();
+///
+/// assert_eq!(codepoint, 0x2260);
+/// assert_eq!(length, 3);
+/// # }
+/// ```
+pub fn decode_u32(bytes: &[u8]) -> Result<(u32, usize), DecodeError> {
+    let lead = *bytes.first().ok_or(DecodeError::UnexpectedEnd)?;
+    let length = decode_length(lead)?;
+
+    if bytes.len() < length {
+        return Err(DecodeError::UnexpectedEnd);
+    }
+
+    let mut acc = (lead & lead_data_mask(length)) as u32;
+    for &byte in &bytes[1..length] {
+        if byte & 0b1100_0000 != 0b1000_0000 {
+            return Err(DecodeError::InvalidContinuationByte);
+        }
+        acc = (acc << 6) | (byte & 0b0011_1111) as u32;
+    }
+
+    if acc < min_for_length(length) {
+        return Err(DecodeError::Overlong);
+    }
+
+    Ok((acc, length))
+}
+
+/// Returns an iterator that decodes successive extended UTF-8 sequences out of `bytes`,
+/// yielding a [`DecodeError`] (without losing the original bytes already consumed)
+/// should a sequence fail to decode.
+///
+/// ## Example
+/// ```rust
+/// # fn main() {
+/// let bytes = [b'a', 0xc2, 0xa9, 0xe2, 0x89, 0xa0];
+/// let codepoints: Result<_, _> = utf8_rfc2279::decode_iter(&bytes).try_fold(0u32, |acc, c| c.map(|c| acc + c));
+///
+/// assert_eq!(codepoints, Ok('a' as u32 + 0xa9 + 0x2260));
+/// # }
+/// ```
+pub fn decode_iter(bytes: &[u8]) -> Decoder<'_> {
+    Decoder::new(bytes)
+}
+
+/// Returns the total length (in bytes) of the sequence starting with the given lead byte.
+pub(crate) fn decode_length(lead: u8) -> Result<usize, DecodeError> {
+    Ok(match lead {
+        0b0000_0000..=0b0111_1111 => 1,
+        0b1100_0000..=0b1101_1111 => 2,
+        0b1110_0000..=0b1110_1111 => 3,
+        0b1111_0000..=0b1111_0111 => 4,
+        0b1111_1000..=0b1111_1011 => 5,
+        0b1111_1100..=0b1111_1101 => 6,
+        _ => return Err(DecodeError::InvalidLeadByte),
+    })
+}
+
+/// Returns the bit mask selecting the data bits held in a lead byte for a sequence
+/// of the given length.
+fn lead_data_mask(length: usize) -> u8 {
+    match length {
+        1 => 0b0111_1111,
+        2 => 0b0001_1111,
+        3 => 0b0000_1111,
+        4 => 0b0000_0111,
+        5 => 0b0000_0011,
+        _ => 0b0000_0001,
+    }
+}
+
+/// Returns the smallest codepoint that a sequence of the given length is allowed to
+/// represent; anything smaller is an overlong encoding.
+fn min_for_length(length: usize) -> u32 {
+    match length {
+        2 => 0x80,
+        3 => 0x800,
+        4 => 0x10000,
+        5 => 0x200000,
+        6 => 0x4000000,
+        _ => 0,
+    }
+}
+
 /// Helper function that returns the 'header' byte of the input's corresponding byte
 /// sequence, along with the corresponding length (total number of bytes in the sequence).
 ///
@@ -77,7 +256,10 @@ pub fn get_header(input: u32) -> Result<(usize, u8), OverflowError> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{encode_u32, get_header, Codepoint};
+    use crate::{
+        decode_u32, encode_u32, encode_u32_slice, get_header, Codepoint, DecodeError,
+        EncodeError, OverflowError,
+    };
 
     #[test]
     fn header_works() {
@@ -113,4 +295,82 @@ mod tests {
             assert_eq!(encode_u32(input).unwrap(), output)
         }
     }
+
+    #[test]
+    fn encoding_to_slice_works() {
+        #[rustfmt::skip]
+        let tests = [
+            (0xa9,          &[0xc2, 0xa9][..]),
+            (0x2260,        &[0xe2, 0x89, 0xa0][..]),
+            (b'a' as u32,   &[b'a'][..]),
+            (b'\0' as u32,  &[0x00][..]),
+            (0x001FCAFE,    &[0xf7, 0xbc, 0xab, 0xbe][..]),
+            (0x03FCAFEF,    &[0xfb, 0xbf, 0x8a, 0xbf, 0xaf][..]),
+            (0x7DEADA55,    &[0xfd, 0xbd, 0xba, 0xad, 0xa9, 0x95][..]),
+        ];
+
+        for (input, expected) in tests {
+            let mut buf = [0u8; 6];
+            let len = encode_u32_slice(input, &mut buf).unwrap();
+            assert_eq!(&buf[..len], expected);
+        }
+    }
+
+    #[test]
+    fn encoding_to_slice_rejects_short_buffers() {
+        let mut buf = [0u8; 2];
+        assert_eq!(
+            encode_u32_slice(0x2260, &mut buf),
+            Err(EncodeError::BufferTooShort)
+        );
+
+        assert_eq!(
+            encode_u32_slice(0x8000_0000, &mut buf),
+            Err(EncodeError::Overflow(OverflowError))
+        );
+    }
+
+    #[test]
+    fn decoding_works() {
+        #[rustfmt::skip]
+        let tests = [
+            (&[0xc2, 0xa9][..],                     0xa9),
+            (&[0xe2, 0x89, 0xa0][..],                0x2260),
+            (&[b'a'][..],                            b'a' as u32),
+            (&[0x00][..],                            b'\0' as u32),
+            (&[0xf7, 0xbc, 0xab, 0xbe][..],          0x001FCAFE),
+            (&[0xfb, 0xbf, 0x8a, 0xbf, 0xaf][..],    0x03FCAFEF),
+            (&[0xfd, 0xbd, 0xba, 0xad, 0xa9, 0x95][..], 0x7DEADA55),
+        ];
+
+        for (bytes, codepoint) in tests {
+            assert_eq!(decode_u32(bytes).unwrap(), (codepoint, bytes.len()));
+        }
+    }
+
+    #[test]
+    fn decoding_roundtrips_with_encoding() {
+        for input in [0, 1, 0xa9, 0x2260, 0x001FCAFE, 0x03FCAFEF, 0x7DEADA55] {
+            let encoded = encode_u32(input).unwrap();
+            let (decoded, len) = decode_u32(&encoded).unwrap();
+
+            assert_eq!(decoded, input);
+            assert_eq!(len, encoded.len());
+        }
+    }
+
+    #[test]
+    fn decoding_rejects_bad_input() {
+        assert_eq!(decode_u32(&[]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(decode_u32(&[0xe2, 0x89]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(
+            decode_u32(&[0xe2, 0x89, 0x00]),
+            Err(DecodeError::InvalidContinuationByte)
+        );
+        assert_eq!(decode_u32(&[0x80]), Err(DecodeError::InvalidLeadByte));
+        assert_eq!(decode_u32(&[0xfe]), Err(DecodeError::InvalidLeadByte));
+        assert_eq!(decode_u32(&[0xff]), Err(DecodeError::InvalidLeadByte));
+        // 0xc0 0x80 would decode to 0, but 0 fits in a single byte.
+        assert_eq!(decode_u32(&[0xc0, 0x80]), Err(DecodeError::Overlong));
+    }
 }